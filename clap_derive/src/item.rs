@@ -20,8 +20,8 @@ use proc_macro_error::abort;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::DeriveInput;
 use syn::{
-    self, ext::IdentExt, spanned::Spanned, Attribute, Field, Ident, LitStr, MetaNameValue, Type,
-    Variant,
+    self, ext::IdentExt, spanned::Spanned, Attribute, Expr, ExprAssign, Field, Ident, LitStr,
+    MetaNameValue, Type, Variant,
 };
 
 use crate::attr::*;
@@ -44,6 +44,8 @@ pub struct Item {
     deprecations: Vec<Deprecation>,
     value_parser: Option<ValueParser>,
     action: Option<Action>,
+    parser: Option<Parser>,
+    rename: Option<Span>,
     verbatim_doc_comment: bool,
     next_display_order: Option<Method>,
     next_help_heading: Option<Method>,
@@ -103,6 +105,12 @@ impl Item {
                 "`action` attribute is only allowed on fields"
             );
         }
+        if let Some(parser) = res.parser.as_ref() {
+            abort!(parser.span(), "`parse` attribute is only allowed on fields");
+        }
+        if let Some(span) = res.rename {
+            abort!(span, "`rename` attribute is only allowed on fields");
+        }
 
         res
     }
@@ -119,6 +127,10 @@ impl Item {
         res.push_attrs(&variant.attrs);
         res.push_doc_comment(&variant.attrs, "about");
 
+        if let Some(span) = res.rename {
+            abort!(span, "`rename` attribute is only allowed on fields");
+        }
+
         match &*res.kind {
             Kind::Flatten => {
                 if let Some(value_parser) = res.value_parser.as_ref() {
@@ -133,6 +145,9 @@ impl Item {
                         "`action` attribute is not allowed for flattened entry"
                     );
                 }
+                if let Some(parser) = res.parser.as_ref() {
+                    abort!(parser.span(), "`parse` attribute is not allowed for flattened entry");
+                }
                 if res.has_explicit_methods() {
                     abort!(
                         res.kind.span(),
@@ -157,6 +172,9 @@ impl Item {
                         "`action` attribute is not allowed for subcommand"
                     );
                 }
+                if let Some(parser) = res.parser.as_ref() {
+                    abort!(parser.span(), "`parse` attribute is not allowed for subcommand");
+                }
 
                 use syn::Fields::*;
                 use syn::FieldsUnnamed;
@@ -235,6 +253,12 @@ impl Item {
                 "`action` attribute is only allowed on fields"
             );
         }
+        if let Some(parser) = res.parser.as_ref() {
+            abort!(parser.span(), "`parse` attribute is only allowed on fields");
+        }
+        if let Some(span) = res.rename {
+            abort!(span, "`rename` attribute is only allowed on fields");
+        }
 
         res
     }
@@ -271,6 +295,12 @@ impl Item {
                         "`action` attribute is not allowed for flattened entry"
                     );
                 }
+                if let Some(parser) = res.parser.as_ref() {
+                    abort!(parser.span(), "`parse` attribute is not allowed for flattened entry");
+                }
+                if let Some(span) = res.rename {
+                    abort!(span, "`rename` attribute is not allowed for flattened entry");
+                }
                 if res.has_explicit_methods() {
                     abort!(
                         res.kind.span(),
@@ -295,6 +325,12 @@ impl Item {
                         "`action` attribute is not allowed for subcommand"
                     );
                 }
+                if let Some(parser) = res.parser.as_ref() {
+                    abort!(parser.span(), "`parse` attribute is not allowed for subcommand");
+                }
+                if let Some(span) = res.rename {
+                    abort!(span, "`rename` attribute is not allowed for subcommand");
+                }
                 if res.has_explicit_methods() {
                     abort!(
                         res.kind.span(),
@@ -394,6 +430,8 @@ impl Item {
             deprecations: vec![],
             value_parser: None,
             action: None,
+            parser: None,
+            rename: None,
             verbatim_doc_comment: false,
             next_display_order: None,
             next_help_heading: None,
@@ -418,6 +456,81 @@ impl Item {
         }
     }
 
+    /// Lower a structopt-style `#[arg(parse(...))]` directive into a [`Parser`].
+    ///
+    /// Accepts the classic `ParserKind` family (`from_str`, `try_from_str`, `from_os_str`,
+    /// `try_from_os_str`, `from_occurrences`, `from_flag`), each spelled either bare
+    /// (`parse(from_occurrences)`) or with an explicit function (`parse(try_from_str = parse_fn)`);
+    /// `from_occurrences` is the exception and only accepts the bare form.
+    fn push_parser(&mut self, attr: &ClapAttr) {
+        let tokens = match &attr.value {
+            Some(AttrValue::Call(tokens)) => tokens.clone(),
+            _ => abort!(
+                attr.name.clone(),
+                "`parse` must be used with a parser kind, e.g. `parse(from_str)` or `parse(try_from_str = parser_fn)`"
+            ),
+        };
+        let arg = tokens.into_iter().next().unwrap_or_else(|| {
+            abort!(
+                attr.name.clone(),
+                "`parse` must be used with a parser kind, e.g. `parse(from_str)` or `parse(try_from_str = parser_fn)`"
+            )
+        });
+
+        let (kind_ident, func) = match syn::parse2::<ExprAssign>(arg.clone()) {
+            Ok(assign) => {
+                let kind_ident = match *assign.left {
+                    Expr::Path(ref path) if path.path.get_ident().is_some() => {
+                        path.path.get_ident().unwrap().clone()
+                    }
+                    _ => abort!(assign.left, "expected a parser kind, e.g. `try_from_str`"),
+                };
+                let right = *assign.right;
+                (kind_ident, Some(quote!(#right)))
+            }
+            Err(_) => {
+                let kind_ident = syn::parse2::<Ident>(arg.clone())
+                    .unwrap_or_else(|_| abort!(arg, "expected a parser kind, e.g. `try_from_str`"));
+                (kind_ident, None)
+            }
+        };
+
+        let kind = match kind_ident.to_string().as_str() {
+            "from_str" => ParserKind::FromStr,
+            "try_from_str" => ParserKind::TryFromStr,
+            "from_os_str" => ParserKind::FromOsStr,
+            "try_from_os_str" => ParserKind::TryFromOsStr,
+            "from_occurrences" => ParserKind::FromOccurrences,
+            "from_flag" => ParserKind::FromFlag,
+            other => abort!(kind_ident, "unsupported `parse` kind `{}`", other),
+        };
+
+        // Unlike `from_flag`, `from_occurrences` has no post-processing function: the count is
+        // used as-is, so `parse(from_occurrences = some_fn)` would silently drop `some_fn`.
+        if kind == ParserKind::FromOccurrences && func.is_some() {
+            abort!(
+                kind_ident,
+                "`from_occurrences` does not take a function, only `from_flag` does"
+            );
+        }
+
+        // A bare `parse(try_from_str)` (no path) falls back to `str::parse`/`TryFrom`/`FromStr`
+        // on the field's type, the same as if no `parse`/`value_parser` attribute were given.
+
+        self.deprecations.push(Deprecation {
+            span: attr.name.clone().span(),
+            id: "parse_attribute",
+            version: "4.0.0",
+            description: "`parse(...)` has been deprecated in favor of `value_parser` and `action`"
+                .to_owned(),
+        });
+
+        self.parser = Some(Parser {
+            kind: Sp::new(kind, kind_ident.span()),
+            func,
+        });
+    }
+
     fn push_attrs(&mut self, attrs: &[Attribute]) {
         let parsed = ClapAttr::parse_all(attrs);
 
@@ -475,6 +588,16 @@ impl Item {
             if let Some(kind) = kind {
                 self.set_kind(kind);
             }
+
+            // Resolve `rename` here, ahead of the main loop below, so `self.casing` is already
+            // final by the time that loop bakes it into a bare `long`/`short` method — otherwise
+            // `#[arg(long, rename = "...")]` would bake the old casing in before `rename` ever
+            // ran, while `#[arg(rename = "...", long)]` would happen to work.
+            if attr.magic == Some(MagicAttrName::Rename) {
+                let lit = attr.lit_str_or_abort();
+                self.casing = CasingStyle::from_lit(lit);
+                self.rename = Some(attr.name.clone().span());
+            }
         }
 
         for attr in &parsed {
@@ -502,6 +625,17 @@ impl Item {
                 _ => {}
             }
 
+            if attr.magic == Some(MagicAttrName::Parse) {
+                self.push_parser(attr);
+                continue;
+            }
+
+            // Already fully resolved in the pre-pass above, ahead of any `long`/`short` in this
+            // same loop that reads `self.casing`.
+            if attr.magic == Some(MagicAttrName::Rename) {
+                continue;
+            }
+
             if let Some(AttrValue::Call(tokens)) = &attr.value {
                 // Force raw mode with method call syntax
                 self.push_method(attr.name.clone(), quote!(#(#tokens),*));
@@ -549,10 +683,44 @@ impl Item {
                     );
                 }
 
+                // `os_value_parser` is sugar for `value_parser` that signals the parser consumes
+                // `&OsStr` rather than `&str`; clap's `.value_parser()` builder method already
+                // accepts either, so it lowers to the same `Method`.
+                Some(MagicAttrName::OsValueParser) => {
+                    let expr = attr.value_or_abort();
+                    self.value_parser = Some(ValueParser::Explicit(Method::new(
+                        Ident::new("value_parser", attr.name.clone().span()),
+                        quote!(#expr),
+                    )));
+                }
+
                 Some(MagicAttrName::ValueEnum) if attr.value.is_none() => {
                     self.is_enum = true
                 }
 
+                // structopt's `parse(from_occurrences)` replacement: `-vvv` -> `3`.
+                Some(MagicAttrName::Count) if attr.value.is_none() => {
+                    if let Some(ty) = self.ty.as_ref() {
+                        let is_integer = [
+                            "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+                            "i128", "isize",
+                        ]
+                        .iter()
+                        .any(|int_ty| is_simple_ty(ty, int_ty));
+                        if !is_integer {
+                            abort!(
+                                attr.name.clone(),
+                                "`#[arg(count)]` can only be used on integer types"
+                            );
+                        }
+                    }
+
+                    self.action = Some(Action::Explicit(Method::new(
+                        Ident::new("action", attr.name.clone().span()),
+                        quote!(clap::ArgAction::Count),
+                    )));
+                }
+
                 Some(MagicAttrName::VerbatimDocComment) if attr.value.is_none() => {
                     self.verbatim_doc_comment = true
                 }
@@ -577,6 +745,9 @@ impl Item {
                     }
                 }
 
+                // A bare `#[arg(default_value_t)]` (no expression) synthesizes
+                // `<#ty as Default>::default()`, mirroring `DefaultValueOsT` below so the two
+                // `*_t`/`*_os_t` code paths stay consistent.
                 Some(MagicAttrName::DefaultValueT) => {
                     let ty = if let Some(ty) = self.ty.as_ref() {
                         ty
@@ -633,26 +804,27 @@ impl Item {
                     };
                     let expr = attr.value_or_abort();
 
-                    let container_type = Ty::from_syn_ty(ty);
-                    if *container_type != Ty::Vec {
+                    let inner_type = collection_inner_type(ty).unwrap_or_else(|| {
                         abort!(
                             attr.name.clone(),
-                            "#[arg(default_values_t)] can be used only on Vec types";
+                            "#[arg(default_values_t)] can be used only on `Vec`, `HashSet`, \
+                            `BTreeSet`, `VecDeque`, or array types";
 
                             note = "see \
                                 https://github.com/clap-rs/clap/blob/master/examples/derive_ref/README.md#magic-attributes")
-                    }
-                    let inner_type = inner_type(ty);
+                    });
 
-                    // Use `Borrow<#inner_type>` so we accept `&Vec<#inner_type>` and
-                    // `Vec<#inner_type>`.
+                    // Use `Borrow<#inner_type>` so we accept the collection itself or a
+                    // reference to it, and return an iterator (rather than collecting into a
+                    // `Vec`) so any of `Vec`/`HashSet`/`BTreeSet`/`VecDeque`/array can feed
+                    // `default_values` directly.
                     let val = if parsed
                         .iter()
                         .any(|a| a.magic == Some(MagicAttrName::ValueEnum))
                     {
                         quote_spanned!(attr.name.clone().span()=> {
                             {
-                                fn iter_to_vals<T>(iterable: impl IntoIterator<Item = T>) -> impl Iterator<Item=String>
+                                fn values<T>(iterable: impl IntoIterator<Item = T>) -> impl Iterator<Item=String>
                                 where
                                     T: ::std::borrow::Borrow<#inner_type>
                                 {
@@ -663,21 +835,20 @@ impl Item {
                                         })
                                 }
 
-                                iter_to_vals(#expr)
+                                values(#expr)
                             }
                         })
                     } else {
                         quote_spanned!(attr.name.clone().span()=> {
                             {
-                                fn iter_to_vals<T>(iterable: impl IntoIterator<Item = T>) -> Vec<String>
+                                fn values<T>(iterable: impl IntoIterator<Item = T>) -> impl Iterator<Item=String>
                                 where
                                     T: ::std::borrow::Borrow<#inner_type>
                                 {
-                                    iterable.into_iter().map(|val| val.borrow().to_string()).collect()
-
+                                    iterable.into_iter().map(|val| val.borrow().to_string())
                                 }
 
-                                iter_to_vals(#expr)
+                                values(#expr)
                             }
                         })
                     };
@@ -841,7 +1012,8 @@ impl Item {
 
                 // Directives that never receive a value
                 Some(MagicAttrName::ValueEnum)
-                | Some(MagicAttrName::VerbatimDocComment) => {
+                | Some(MagicAttrName::VerbatimDocComment)
+                | Some(MagicAttrName::Count) => {
                     let expr = attr.value_or_abort();
                     abort!(expr, "attribute `{}` does not accept a value", attr.name);
                 }
@@ -853,6 +1025,10 @@ impl Item {
                 | Some(MagicAttrName::Flatten)
                 | Some(MagicAttrName::Skip) => {
                 }
+
+                // Handled above (`push_parser`, and the `rename` pre-pass) before this match is
+                // reached.
+                Some(MagicAttrName::Parse) | Some(MagicAttrName::Rename) => unreachable!(),
             }
         }
     }
@@ -969,45 +1145,34 @@ impl Item {
     }
 
     pub fn value_parser(&self, field_type: &Type) -> Method {
-        self.value_parser
-            .clone()
-            .map(|p| {
-                let inner_type = inner_type(field_type);
-                p.resolve(inner_type)
-            })
-            .unwrap_or_else(|| {
-                let inner_type = inner_type(field_type);
-                if let Some(action) = self.action.as_ref() {
-                    let span = action.span();
-                    default_value_parser(inner_type, span)
-                } else {
-                    let span = self
-                        .action
-                        .as_ref()
-                        .map(|a| a.span())
-                        .unwrap_or_else(|| self.kind.span());
-                    default_value_parser(inner_type, span)
-                }
-            })
+        let inner_type = inner_type(field_type);
+        if let Some(value_parser) = self.value_parser.as_ref() {
+            return value_parser.clone().resolve(inner_type);
+        }
+        if let Some(parser) = self.parser.as_ref() {
+            return parser.value_parser_method(inner_type);
+        }
+        let span = self
+            .action
+            .as_ref()
+            .map(|a| a.span())
+            .unwrap_or_else(|| self.kind.span());
+        default_value_parser(inner_type, span)
     }
 
     pub fn action(&self, field_type: &Type) -> Method {
-        self.action
-            .clone()
-            .map(|p| p.resolve(field_type))
-            .unwrap_or_else(|| {
-                if let Some(value_parser) = self.value_parser.as_ref() {
-                    let span = value_parser.span();
-                    default_action(field_type, span)
-                } else {
-                    let span = self
-                        .value_parser
-                        .as_ref()
-                        .map(|a| a.span())
-                        .unwrap_or_else(|| self.kind.span());
-                    default_action(field_type, span)
-                }
-            })
+        if let Some(action) = self.action.as_ref() {
+            return action.clone().resolve(field_type);
+        }
+        if let Some(parser) = self.parser.as_ref() {
+            return parser.action_method();
+        }
+        let span = self
+            .value_parser
+            .as_ref()
+            .map(|a| a.span())
+            .unwrap_or_else(|| self.kind.span());
+        default_action(field_type, span)
     }
 
     pub fn kind(&self) -> Sp<Kind> {
@@ -1058,8 +1223,50 @@ impl ValueParser {
     }
 }
 
+/// Element type of a `default_values_t`-eligible collection: `Vec<T>`, `HashSet<T>`,
+/// `BTreeSet<T>`, `VecDeque<T>`, or a fixed-size array `[T; N]`.
+fn collection_inner_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Array(array) => Some(&*array.elem),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            if !matches!(
+                segment.ident.to_string().as_str(),
+                "Vec" | "HashSet" | "BTreeSet" | "VecDeque"
+            ) {
+                return None;
+            }
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => {
+                    args.args.iter().find_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// `PathBuf`/`OsString` fields are special-cased onto clap's dedicated `OsStr`-based parsers
+// explicitly, rather than trusting `clap::value_parser!`'s type-name dispatch to do the right
+// thing, so non-UTF-8 paths round-trip losslessly instead of erroring.
 fn default_value_parser(inner_type: &Type, span: Span) -> Method {
     let func = Ident::new("value_parser", span);
+    if is_simple_ty(inner_type, "PathBuf") {
+        return Method::new(
+            func,
+            quote_spanned! { span=> clap::builder::PathBufValueParser::new() },
+        );
+    }
+    if is_simple_ty(inner_type, "OsString") {
+        return Method::new(
+            func,
+            quote_spanned! { span=> clap::builder::OsStringValueParser::new() },
+        );
+    }
     Method::new(
         func,
         quote_spanned! { span=>
@@ -1123,6 +1330,122 @@ fn default_action(field_type: &Type, span: Span) -> Method {
     Method::new(func, args)
 }
 
+/// A parser lowered from structopt's `parse(...)` shorthand.
+///
+/// Mirrors the `Parser`/`ParserKind` design from `structopt-derive`, translating the classic
+/// parser kinds into the `value_parser`/`action` pair this derive emits by default.
+#[derive(Clone)]
+struct Parser {
+    kind: Sp<ParserKind>,
+    func: Option<TokenStream>,
+}
+
+impl Parser {
+    fn span(&self) -> Span {
+        self.kind.span()
+    }
+
+    fn value_parser_method(&self, inner_type: &Type) -> Method {
+        let span = self.span();
+        let func = match (&self.func, *self.kind) {
+            // A bare `parse(try_from_str)` etc. (no path) behaves as if `parse` were never
+            // given: fall back to `str::parse`/`TryFrom`/`FromStr` via `clap::value_parser!`.
+            (None, ParserKind::FromStr | ParserKind::TryFromStr)
+            | (None, ParserKind::FromOsStr | ParserKind::TryFromOsStr) => {
+                return default_value_parser(inner_type, span);
+            }
+            (Some(func), _) => func,
+            // A bare `parse(from_occurrences)`/`parse(from_flag)` has no post-processing
+            // function, so the count/bool value is used as-is.
+            (None, ParserKind::FromOccurrences | ParserKind::FromFlag) => {
+                return Method::new(
+                    Ident::new("value_parser", span),
+                    quote_spanned! { span=> clap::value_parser!(#inner_type) },
+                );
+            }
+        };
+
+        let args = match *self.kind {
+            ParserKind::FromStr => {
+                quote_spanned! { span=>
+                    clap::builder::ValueParser::new(move |s: &str| -> ::std::result::Result<#inner_type, ::std::convert::Infallible> {
+                        ::std::result::Result::Ok((#func)(s))
+                    })
+                }
+            }
+            ParserKind::TryFromStr => {
+                quote_spanned! { span=>
+                    clap::builder::ValueParser::new(move |s: &str| {
+                        (#func)(s).map_err(|e| clap::Error::raw(clap::error::ErrorKind::ValueValidation, e))
+                    })
+                }
+            }
+            // clap only adapts `&str` closures into value parsers, so the `OsStr` kinds map over
+            // `OsStringValueParser`, which hands over the raw `OsString` without a UTF-8 check.
+            ParserKind::FromOsStr => {
+                quote_spanned! { span=>
+                    clap::builder::TypedValueParser::map(
+                        clap::builder::OsStringValueParser::new(),
+                        move |s: ::std::ffi::OsString| -> #inner_type { (#func)(&s) },
+                    )
+                }
+            }
+            ParserKind::TryFromOsStr => {
+                quote_spanned! { span=>
+                    clap::builder::TypedValueParser::try_map(
+                        clap::builder::OsStringValueParser::new(),
+                        move |s: ::std::ffi::OsString| {
+                            (#func)(&s).map_err(|e| clap::Error::raw(clap::error::ErrorKind::ValueValidation, e))
+                        },
+                    )
+                }
+            }
+            ParserKind::FromOccurrences => {
+                quote_spanned! { span=> clap::value_parser!(#inner_type) }
+            }
+            // `ArgAction::SetTrue` backs the flag with `default_value("false")` and
+            // `default_missing_value("true")`, so the value this parser sees really is the
+            // literal string `"true"`/`"false"`; post-process it through `func` to land on the
+            // field's actual type (e.g. a presence flag populating an enum).
+            ParserKind::FromFlag => {
+                quote_spanned! { span=>
+                    clap::builder::ValueParser::new(move |s: &str| -> ::std::result::Result<#inner_type, ::std::convert::Infallible> {
+                        ::std::result::Result::Ok((#func)(s == "true"))
+                    })
+                }
+            }
+        };
+
+        Method::new(Ident::new("value_parser", span), args)
+    }
+
+    fn action_method(&self) -> Method {
+        let span = self.span();
+        let args = match *self.kind {
+            ParserKind::FromOccurrences => quote_spanned! { span=> clap::ArgAction::Count },
+            ParserKind::FromFlag => quote_spanned! { span=> clap::ArgAction::SetTrue },
+            ParserKind::FromStr
+            | ParserKind::TryFromStr
+            | ParserKind::FromOsStr
+            | ParserKind::TryFromOsStr => quote_spanned! { span=> clap::ArgAction::Set },
+        };
+
+        Method::new(Ident::new("action", span), args)
+    }
+}
+
+/// The classic structopt `ParserKind` family, recast as a thin derive-time lowering over
+/// clap's `value_parser`/`action` machinery.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ParserKind {
+    FromStr,
+    TryFromStr,
+    FromOsStr,
+    TryFromOsStr,
+    FromOccurrences,
+    FromFlag,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 pub enum Kind {
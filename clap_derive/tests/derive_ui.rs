@@ -0,0 +1,9 @@
+//! Compile-time checks for the derive's magic attributes: `tests/ui/*.rs` must compile, and
+//! `tests/ui-fail/*.rs` must fail with the abort message recorded in the matching `.stderr`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/*.rs");
+    t.compile_fail("tests/ui-fail/*.rs");
+}
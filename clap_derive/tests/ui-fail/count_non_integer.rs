@@ -0,0 +1,12 @@
+// `#[arg(count)]` only makes sense on an integer field; anything else should abort at
+// derive-time rather than fail confusingly later.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, count)]
+    verbose: String,
+}
+
+fn main() {}
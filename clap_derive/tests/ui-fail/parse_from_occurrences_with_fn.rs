@@ -0,0 +1,16 @@
+// Unlike `from_flag`, `from_occurrences` has no post-processing function: passing one would be
+// silently discarded, so the derive aborts instead.
+
+use clap::Parser;
+
+fn double(n: u8) -> u8 {
+    n * 2
+}
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(short, long, parse(from_occurrences = double))]
+    verbose: u8,
+}
+
+fn main() {}
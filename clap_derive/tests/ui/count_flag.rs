@@ -0,0 +1,14 @@
+// `#[arg(count)]` is shorthand for `.action(ArgAction::Count)` on an integer field.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(short, long, count)]
+    verbose: u8,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "-vvv"]);
+    assert_eq!(opt.verbose, 3);
+}
@@ -0,0 +1,14 @@
+// `default_values_t` also accepts a fixed-size array field type.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, default_values_t = [1, 2, 3])]
+    values: [u32; 3],
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test"]);
+    assert_eq!(opt.values, [1, 2, 3]);
+}
@@ -0,0 +1,20 @@
+// `default_values_t` accepts `HashSet<T>` (and `BTreeSet`/`VecDeque`/arrays) as the field type,
+// not just `Vec<T>`.
+
+use clap::Parser;
+use std::collections::HashSet;
+
+fn defaults() -> HashSet<u32> {
+    HashSet::from([1, 2, 3])
+}
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, default_values_t = defaults())]
+    values: HashSet<u32>,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test"]);
+    assert_eq!(opt.values, HashSet::from([1, 2, 3]));
+}
@@ -0,0 +1,31 @@
+// `parse(from_flag = ...)` lets a single presence flag populate a richer type than `bool`.
+
+use clap::Parser;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Mode {
+    Quiet,
+    Normal,
+}
+
+fn mode_from_flag(quiet: bool) -> Mode {
+    if quiet {
+        Mode::Quiet
+    } else {
+        Mode::Normal
+    }
+}
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, parse(from_flag = mode_from_flag))]
+    quiet: Mode,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test"]);
+    assert_eq!(opt.quiet, Mode::Normal);
+
+    let opt = Opt::parse_from(["test", "--quiet"]);
+    assert_eq!(opt.quiet, Mode::Quiet);
+}
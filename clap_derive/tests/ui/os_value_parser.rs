@@ -0,0 +1,15 @@
+// `#[arg(os_value_parser = ...)]` is sugar for `value_parser` that documents intent to parse
+// straight from `&OsStr`; `.value_parser()` accepts either kind of parser the same way.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, os_value_parser = clap::builder::OsStringValueParser::new())]
+    raw: std::ffi::OsString,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--raw", "hello"]);
+    assert_eq!(opt.raw, "hello");
+}
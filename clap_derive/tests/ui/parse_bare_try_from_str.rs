@@ -0,0 +1,18 @@
+// A bare `parse(try_from_str)` (no function) falls back to the default `value_parser!` lowering,
+// the same as if `parse`/`value_parser` had never been given.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, parse(try_from_str))]
+    count: u32,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--count", "42"]);
+    assert_eq!(opt.count, 42);
+
+    let err = Opt::try_parse_from(["test", "--count", "nope"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+}
@@ -0,0 +1,18 @@
+// `parse(from_str = ...)` lowers to a `value_parser`/`action` pair built from the given function.
+
+use clap::Parser;
+
+fn parse_hex(s: &str) -> u32 {
+    u32::from_str_radix(s.trim_start_matches("0x"), 16).unwrap_or_default()
+}
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, parse(from_str = parse_hex))]
+    mask: u32,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--mask", "0xff"]);
+    assert_eq!(opt.mask, 0xff);
+}
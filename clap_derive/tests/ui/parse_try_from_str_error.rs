@@ -0,0 +1,27 @@
+// `parse(try_from_str = ...)` surfaces the function's `Err` as a `clap::Error`
+// (`ErrorKind::ValueValidation`), not a panic.
+
+use clap::Parser;
+
+fn parse_even(s: &str) -> Result<u32, String> {
+    let n: u32 = s.parse().map_err(|_| format!("`{s}` is not a number"))?;
+    if n % 2 == 0 {
+        Ok(n)
+    } else {
+        Err(format!("`{n}` is not even"))
+    }
+}
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, parse(try_from_str = parse_even))]
+    count: u32,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--count", "4"]);
+    assert_eq!(opt.count, 4);
+
+    let err = Opt::try_parse_from(["test", "--count", "3"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+}
@@ -0,0 +1,21 @@
+// `PathBuf`/`OsString` fields default to clap's `OsStr`-based parsers, so non-UTF-8 input
+// round-trips losslessly instead of erroring (no `value_parser`/`os_value_parser` needed).
+
+use clap::Parser;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long)]
+    path: PathBuf,
+
+    #[arg(long)]
+    raw: OsString,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--path", "/tmp/file", "--raw", "hello"]);
+    assert_eq!(opt.path, PathBuf::from("/tmp/file"));
+    assert_eq!(opt.raw, OsString::from("hello"));
+}
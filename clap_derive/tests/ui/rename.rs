@@ -0,0 +1,19 @@
+// `#[arg(rename = "...")]` overrides the casing for just this field's long name, regardless of
+// whether `rename` is listed before or after `long` in the attribute list.
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Opt {
+    #[arg(long, rename = "camelCase")]
+    first_value: u32,
+
+    #[arg(rename = "camelCase", long)]
+    second_value: u32,
+}
+
+fn main() {
+    let opt = Opt::parse_from(["test", "--firstValue", "1", "--secondValue", "2"]);
+    assert_eq!(opt.first_value, 1);
+    assert_eq!(opt.second_value, 2);
+}